@@ -1,9 +1,11 @@
 use blake2_rfc::blake2b::Blake2b;
 use byteorder::{LittleEndian, WriteBytesExt};
+use sha2::{Digest, Sha256};
+use std::io::{self, Write};
 
 use super::{
     components::{Amount, Script},
-    Transaction, OVERWINTER_VERSION_GROUP_ID, SAPLING_TX_VERSION,
+    Transaction, OVERWINTER_VERSION_GROUP_ID, SAPLING_TX_VERSION, SAPLING_VERSION_GROUP_ID,
 };
 
 const ZCASH_SIGHASH_PERSONALIZATION_PREFIX: &'static [u8; 12] = b"ZcashSigHash";
@@ -11,33 +13,19 @@ const ZCASH_PREVOUTS_HASH_PERSONALIZATION: &'static [u8; 16] = b"ZcashPrevoutHas
 const ZCASH_SEQUENCE_HASH_PERSONALIZATION: &'static [u8; 16] = b"ZcashSequencHash";
 const ZCASH_OUTPUTS_HASH_PERSONALIZATION: &'static [u8; 16] = b"ZcashOutputsHash";
 const ZCASH_JOINSPLITS_HASH_PERSONALIZATION: &'static [u8; 16] = b"ZcashJSplitsHash";
+const ZCASH_SSPENDS_HASH_PERSONALIZATION: &'static [u8; 16] = b"ZcashSSpendsHash";
+const ZCASH_SOUTPUTS_HASH_PERSONALIZATION: &'static [u8; 16] = b"ZcashSOutputHash";
 
 const SIGHASH_NONE: u32 = 2;
 const SIGHASH_SINGLE: u32 = 3;
 const SIGHASH_MASK: u32 = 0x1f;
 const SIGHASH_ANYONECANPAY: u32 = 0x80;
 
-macro_rules! update_u32 {
-    ($h:expr, $value:expr, $tmp:expr) => {
-        (&mut $tmp[..4]).write_u32::<LittleEndian>($value).unwrap();
-        $h.update(&$tmp[..4]);
-    };
-}
-
-macro_rules! update_hash {
-    ($h:expr, $cond:expr, $value:expr) => {
-        if $cond {
-            $h.update(&$value);
-        } else {
-            $h.update(&[0; 32]);
-        }
-    };
-}
-
 #[derive(PartialEq)]
 enum SigHashVersion {
     Sprout,
     Overwinter,
+    Sapling,
 }
 
 impl SigHashVersion {
@@ -45,6 +33,7 @@ impl SigHashVersion {
         if tx.overwintered {
             match tx.version_group_id {
                 OVERWINTER_VERSION_GROUP_ID => SigHashVersion::Overwinter,
+                SAPLING_VERSION_GROUP_ID => SigHashVersion::Sapling,
                 _ => unimplemented!(),
             }
         } else {
@@ -102,6 +91,274 @@ fn joinsplits_hash(tx: &Transaction) -> Vec<u8> {
     h.finalize().as_ref().to_vec()
 }
 
+fn shielded_spends_hash(tx: &Transaction) -> Vec<u8> {
+    let mut data = Vec::with_capacity(tx.shielded_spends.len() * (32 + 32 + 32 + 32 + 192));
+    for spend in &tx.shielded_spends {
+        data.extend_from_slice(&spend.cv.to_bytes());
+        data.extend_from_slice(&spend.anchor.to_bytes());
+        data.extend_from_slice(&spend.nullifier);
+        data.extend_from_slice(&spend.rk.to_bytes());
+        data.extend_from_slice(&spend.zkproof);
+    }
+    let mut h = Blake2b::with_params(32, &[], &[], ZCASH_SSPENDS_HASH_PERSONALIZATION);
+    h.update(&data);
+    h.finalize().as_ref().to_vec()
+}
+
+fn shielded_outputs_hash(tx: &Transaction) -> Vec<u8> {
+    let mut data = Vec::with_capacity(tx.shielded_outputs.len() * (32 + 32 + 32 + 580 + 80));
+    for out in &tx.shielded_outputs {
+        data.extend_from_slice(&out.cv.to_bytes());
+        data.extend_from_slice(&out.cmu.to_bytes());
+        data.extend_from_slice(&out.ephemeral_key.to_bytes());
+        data.extend_from_slice(&out.enc_ciphertext);
+        data.extend_from_slice(&out.out_ciphertext);
+    }
+    let mut h = Blake2b::with_params(32, &[], &[], ZCASH_SOUTPUTS_HASH_PERSONALIZATION);
+    h.update(&data);
+    h.finalize().as_ref().to_vec()
+}
+
+/// The intermediate sighash components that feed the final personalized
+/// BLAKE2b preimage computed by [`signature_hash`]. Exposed so that
+/// callers which cannot run this function internally (such as hardware
+/// signers) can assemble and hash the preimage on their own.
+pub struct SigHashComponents {
+    pub consensus_branch_id: u32,
+    pub header: u32,
+    pub version_group_id: u32,
+    pub prevouts_hash: Vec<u8>,
+    pub sequence_hash: Vec<u8>,
+    pub outputs_hash: Vec<u8>,
+    pub joinsplits_hash: Vec<u8>,
+    pub shielded_spends_hash: Vec<u8>,
+    pub shielded_outputs_hash: Vec<u8>,
+    pub lock_time: u32,
+    pub expiry_height: u32,
+    pub value_balance: i64,
+    pub hash_type: u32,
+    /// Whether `tx` is a Sapling transaction. ZIP-243 adds the shielded
+    /// hashes and `value_balance` to the preimage on top of the ZIP-143
+    /// (Overwinter) fields, so `to_bytes()` needs to know which format to
+    /// emit.
+    is_sapling: bool,
+}
+
+impl SigHashComponents {
+    /// Serializes the components in preimage order. The result still needs
+    /// to be fed to a BLAKE2b instance personalized with `self.consensus_branch_id`
+    /// (see [`signature_hash`] for how the personalization is derived), followed
+    /// by the per-transparent-input suffix when signing a transparent input.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = vec![];
+        data.write_u32::<LittleEndian>(self.header).unwrap();
+        data.write_u32::<LittleEndian>(self.version_group_id).unwrap();
+        data.extend_from_slice(&self.prevouts_hash);
+        data.extend_from_slice(&self.sequence_hash);
+        data.extend_from_slice(&self.outputs_hash);
+        data.extend_from_slice(&self.joinsplits_hash);
+        if self.is_sapling {
+            data.extend_from_slice(&self.shielded_spends_hash);
+            data.extend_from_slice(&self.shielded_outputs_hash);
+        }
+        data.write_u32::<LittleEndian>(self.lock_time).unwrap();
+        data.write_u32::<LittleEndian>(self.expiry_height).unwrap();
+        if self.is_sapling {
+            data.write_i64::<LittleEndian>(self.value_balance).unwrap();
+        }
+        data.write_u32::<LittleEndian>(self.hash_type).unwrap();
+        data
+    }
+}
+
+/// Computes the individual sighash field digests, without taking the final
+/// personalized hash of them. See [`SigHashComponents`].
+pub fn sighash_components(
+    tx: &Transaction,
+    consensus_branch_id: u32,
+    hash_type: u32,
+    transparent_input: Option<(usize, Script, Amount)>,
+) -> SigHashComponents {
+    let sigversion = SigHashVersion::from_tx(tx);
+    assert!(
+        sigversion != SigHashVersion::Sprout,
+        "Sprout transactions do not use the BLAKE2b sighash scheme"
+    );
+
+    let hash_outputs = if (hash_type & SIGHASH_MASK) != SIGHASH_SINGLE
+        && (hash_type & SIGHASH_MASK) != SIGHASH_NONE
+    {
+        outputs_hash(tx)
+    } else if (hash_type & SIGHASH_MASK) == SIGHASH_SINGLE
+        && transparent_input.is_some()
+        && transparent_input.as_ref().unwrap().0 < tx.vout.len()
+    {
+        let mut data = vec![];
+        tx.vout[transparent_input.as_ref().unwrap().0]
+            .write(&mut data)
+            .unwrap();
+        let mut h = Blake2b::with_params(32, &[], &[], ZCASH_OUTPUTS_HASH_PERSONALIZATION);
+        h.update(&data);
+        h.finalize().as_ref().to_vec()
+    } else {
+        vec![0; 32]
+    };
+
+    SigHashComponents {
+        consensus_branch_id,
+        header: tx.header(),
+        version_group_id: tx.version_group_id,
+        prevouts_hash: if hash_type & SIGHASH_ANYONECANPAY == 0 {
+            prevout_hash(tx)
+        } else {
+            vec![0; 32]
+        },
+        sequence_hash: if hash_type & SIGHASH_ANYONECANPAY == 0
+            && (hash_type & SIGHASH_MASK) != SIGHASH_SINGLE
+            && (hash_type & SIGHASH_MASK) != SIGHASH_NONE
+        {
+            sequence_hash(tx)
+        } else {
+            vec![0; 32]
+        },
+        outputs_hash: hash_outputs,
+        joinsplits_hash: if !tx.joinsplits.is_empty() {
+            joinsplits_hash(tx)
+        } else {
+            vec![0; 32]
+        },
+        shielded_spends_hash: if sigversion == SigHashVersion::Sapling
+            && !tx.shielded_spends.is_empty()
+        {
+            shielded_spends_hash(tx)
+        } else {
+            vec![0; 32]
+        },
+        shielded_outputs_hash: if sigversion == SigHashVersion::Sapling
+            && !tx.shielded_outputs.is_empty()
+        {
+            shielded_outputs_hash(tx)
+        } else {
+            vec![0; 32]
+        },
+        lock_time: tx.lock_time,
+        expiry_height: tx.expiry_height,
+        value_balance: if sigversion == SigHashVersion::Sapling {
+            tx.value_balance
+        } else {
+            0
+        },
+        hash_type,
+        is_sapling: sigversion == SigHashVersion::Sapling,
+    }
+}
+
+fn write_compact_size<W: Write>(mut writer: W, n: u64) -> io::Result<()> {
+    if n < 0xfd {
+        writer.write_u8(n as u8)
+    } else if n <= 0xffff {
+        writer.write_u8(0xfd)?;
+        writer.write_u16::<LittleEndian>(n as u16)
+    } else if n <= 0xffff_ffff {
+        writer.write_u8(0xfe)?;
+        writer.write_u32::<LittleEndian>(n as u32)
+    } else {
+        writer.write_u8(0xff)?;
+        writer.write_u64::<LittleEndian>(n)
+    }
+}
+
+/// Legacy (pre-Overwinter) signature hash, as used by the original Zcash
+/// (Sprout) and inherited unchanged from Bitcoin: a double-SHA256 of a
+/// modified serialization of the transaction, with `hash_type` appended.
+fn sprout_signature_hash(
+    tx: &Transaction,
+    hash_type: u32,
+    transparent_input: Option<(usize, Script, Amount)>,
+) -> Vec<u8> {
+    let mask = hash_type & SIGHASH_MASK;
+    let anyone_can_pay = hash_type & SIGHASH_ANYONECANPAY != 0;
+    let n_in = transparent_input.as_ref().map(|(n, _, _)| *n);
+    let script_code = transparent_input.map(|(_, script, _)| script);
+
+    if mask == SIGHASH_SINGLE && n_in.map(|n| n >= tx.vout.len()).unwrap_or(true) {
+        // Inherited from Bitcoin: signing a SIGHASH_SINGLE input with no
+        // corresponding output (including when there is no signed input at
+        // all) returns this sentinel instead of hashing anything.
+        let mut sentinel = vec![0u8; 32];
+        sentinel[0] = 1;
+        return sentinel;
+    }
+
+    let mut data = vec![];
+    data.write_u32::<LittleEndian>(tx.version).unwrap();
+
+    let vin_indices: Vec<usize> = if anyone_can_pay {
+        n_in.into_iter().collect()
+    } else {
+        (0..tx.vin.len()).collect()
+    };
+    write_compact_size(&mut data, vin_indices.len() as u64).unwrap();
+    for i in vin_indices {
+        tx.vin[i].prevout.write(&mut data).unwrap();
+        if Some(i) == n_in {
+            if let Some(ref script) = script_code {
+                script.write(&mut data).unwrap();
+            } else {
+                write_compact_size(&mut data, 0).unwrap();
+            }
+        } else {
+            write_compact_size(&mut data, 0).unwrap();
+        }
+        let sequence = if Some(i) == n_in || (mask != SIGHASH_NONE && mask != SIGHASH_SINGLE) {
+            tx.vin[i].sequence
+        } else {
+            0
+        };
+        data.write_u32::<LittleEndian>(sequence).unwrap();
+    }
+
+    match mask {
+        SIGHASH_NONE => {
+            write_compact_size(&mut data, 0).unwrap();
+        }
+        SIGHASH_SINGLE => {
+            let n = n_in.unwrap();
+            write_compact_size(&mut data, (n + 1) as u64).unwrap();
+            for (i, t_out) in tx.vout.iter().enumerate().take(n + 1) {
+                if i == n {
+                    t_out.write(&mut data).unwrap();
+                } else {
+                    data.write_i64::<LittleEndian>(-1).unwrap();
+                    write_compact_size(&mut data, 0).unwrap();
+                }
+            }
+        }
+        _ => {
+            write_compact_size(&mut data, tx.vout.len() as u64).unwrap();
+            for t_out in &tx.vout {
+                t_out.write(&mut data).unwrap();
+            }
+        }
+    }
+
+    data.write_u32::<LittleEndian>(tx.lock_time).unwrap();
+
+    if tx.version >= 2 {
+        write_compact_size(&mut data, tx.joinsplits.len() as u64).unwrap();
+        for js in &tx.joinsplits {
+            js.write(&mut data).unwrap();
+        }
+        if !tx.joinsplits.is_empty() {
+            data.extend_from_slice(&tx.joinsplit_pubkey);
+        }
+    }
+
+    data.write_u32::<LittleEndian>(hash_type).unwrap();
+
+    Sha256::digest(&Sha256::digest(&data)).to_vec()
+}
+
 pub fn signature_hash(
     tx: &Transaction,
     consensus_branch_id: u32,
@@ -110,50 +367,18 @@ pub fn signature_hash(
 ) -> Vec<u8> {
     let sigversion = SigHashVersion::from_tx(tx);
     match sigversion {
-        SigHashVersion::Overwinter => {
-            let hash_outputs = if (hash_type & SIGHASH_MASK) != SIGHASH_SINGLE
-                && (hash_type & SIGHASH_MASK) != SIGHASH_NONE
-            {
-                outputs_hash(tx)
-            } else if (hash_type & SIGHASH_MASK) == SIGHASH_SINGLE
-                && transparent_input.is_some()
-                && transparent_input.as_ref().unwrap().0 < tx.vout.len()
-            {
-                let mut data = vec![];
-                tx.vout[transparent_input.as_ref().unwrap().0]
-                    .write(&mut data)
-                    .unwrap();
-                let mut h = Blake2b::with_params(32, &[], &[], ZCASH_OUTPUTS_HASH_PERSONALIZATION);
-                h.update(&data);
-                h.finalize().as_ref().to_vec()
-            } else {
-                vec![0; 32]
-            };
+        SigHashVersion::Overwinter | SigHashVersion::Sapling => {
+            let components =
+                sighash_components(tx, consensus_branch_id, hash_type, transparent_input.clone());
 
             let mut personal = [0; 16];
             (&mut personal[..12]).copy_from_slice(ZCASH_SIGHASH_PERSONALIZATION_PREFIX);
             (&mut personal[12..])
-                .write_u32::<LittleEndian>(consensus_branch_id)
+                .write_u32::<LittleEndian>(components.consensus_branch_id)
                 .unwrap();
 
             let mut h = Blake2b::with_params(32, &[], &[], &personal);
-            let mut tmp = [0; 8];
-
-            update_u32!(h, tx.header(), tmp);
-            update_u32!(h, tx.version_group_id, tmp);
-            update_hash!(h, hash_type & SIGHASH_ANYONECANPAY == 0, prevout_hash(tx));
-            update_hash!(
-                h,
-                hash_type & SIGHASH_ANYONECANPAY == 0
-                    && (hash_type & SIGHASH_MASK) != SIGHASH_SINGLE
-                    && (hash_type & SIGHASH_MASK) != SIGHASH_NONE,
-                sequence_hash(tx)
-            );
-            h.update(&hash_outputs);
-            update_hash!(h, !tx.joinsplits.is_empty(), joinsplits_hash(tx));
-            update_u32!(h, tx.lock_time, tmp);
-            update_u32!(h, tx.expiry_height, tmp);
-            update_u32!(h, hash_type, tmp);
+            h.update(&components.to_bytes());
 
             if let Some((n, script_code, amount)) = transparent_input {
                 let mut data = vec![];
@@ -168,6 +393,305 @@ pub fn signature_hash(
 
             h.finalize().as_ref().to_vec()
         }
-        SigHashVersion::Sprout => unimplemented!(),
+        SigHashVersion::Sprout => sprout_signature_hash(tx, hash_type, transparent_input),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use blake2_rfc::blake2b::Blake2b;
+    use byteorder::{LittleEndian, WriteBytesExt};
+
+    use super::super::components::{Amount, OutPoint, Script, TxIn, TxOut};
+    use super::super::{Transaction, OVERWINTER_VERSION_GROUP_ID};
+    use super::{
+        sighash_components, signature_hash, sprout_signature_hash, SigHashComponents,
+        SAPLING_VERSION_GROUP_ID, SIGHASH_ANYONECANPAY, SIGHASH_NONE, SIGHASH_SINGLE,
+        ZCASH_SIGHASH_PERSONALIZATION_PREFIX,
+    };
+
+    const SIGHASH_ALL: u32 = 1;
+
+    fn dummy_sapling_tx() -> Transaction {
+        Transaction {
+            overwintered: true,
+            version: 4,
+            version_group_id: SAPLING_VERSION_GROUP_ID,
+            vin: vec![],
+            vout: vec![],
+            lock_time: 0,
+            expiry_height: 0,
+            value_balance: 0,
+            shielded_spends: vec![],
+            shielded_outputs: vec![],
+            joinsplits: vec![],
+            joinsplit_pubkey: [0; 32],
+            joinsplit_sig: None,
+            binding_sig: None,
+        }
+    }
+
+    // These two check structural invariants of `sighash_components` (what
+    // gets zero-filled, and the byte layout `to_bytes` produces) rather than
+    // a value independently computed outside this module. See
+    // `sapling_preimage_and_signature_hash_known_answer` below for an actual
+    // known-answer vector.
+    #[test]
+    fn sapling_components_zero_fill_empty_shielded_and_joinsplit_fields() {
+        let tx = dummy_sapling_tx();
+        let components = sighash_components(&tx, 0x76b8_09bb, SIGHASH_ALL, None);
+
+        // With no shielded spends/outputs/joinsplits, the preimage carries
+        // the all-zero placeholder rather than a hash of empty data.
+        assert_eq!(components.shielded_spends_hash, vec![0u8; 32]);
+        assert_eq!(components.shielded_outputs_hash, vec![0u8; 32]);
+        assert_eq!(components.joinsplits_hash, vec![0u8; 32]);
+        assert_eq!(components.value_balance, 0);
+        assert_eq!(components.consensus_branch_id, 0x76b8_09bb);
+    }
+
+    #[test]
+    fn sapling_to_bytes_field_order_matches_struct_layout() {
+        let tx = dummy_sapling_tx();
+        let components = sighash_components(&tx, 0x76b8_09bb, SIGHASH_ALL, None);
+        let bytes = components.to_bytes();
+
+        // header(4) + version_group_id(4) + 6 * 32-byte hashes + lock_time(4)
+        // + expiry_height(4) + value_balance(8) + hash_type(4).
+        assert_eq!(bytes.len(), 4 + 4 + 6 * 32 + 4 + 4 + 8 + 4);
+        assert_eq!(&bytes[0..4], &tx.header().to_le_bytes());
+        assert_eq!(&bytes[4..8], &tx.version_group_id.to_le_bytes());
+        assert_eq!(&bytes[bytes.len() - 4..], &SIGHASH_ALL.to_le_bytes());
+    }
+
+    /// Known-answer test for the ZIP-243 preimage and final sighash,
+    /// independently computed (Python `hashlib.blake2b`) from fixed inputs
+    /// rather than derived from this module's own logic.
+    #[test]
+    fn sapling_preimage_and_signature_hash_known_answer() {
+        let components = SigHashComponents {
+            consensus_branch_id: 0x76b8_09bb,
+            header: 0x8000_0004,
+            version_group_id: 0x892f_2085,
+            prevouts_hash: vec![0x11; 32],
+            sequence_hash: vec![0x22; 32],
+            outputs_hash: vec![0x33; 32],
+            joinsplits_hash: vec![0x44; 32],
+            shielded_spends_hash: vec![0x55; 32],
+            shielded_outputs_hash: vec![0x66; 32],
+            lock_time: 0xdead_beef,
+            expiry_height: 0x00ab_cdef,
+            value_balance: -12345,
+            hash_type: 1,
+            is_sapling: true,
+        };
+
+        let expected_preimage: [u8; 220] = [
+            0x04, 0x00, 0x00, 0x80, 0x85, 0x20, 0x2f, 0x89, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+            0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+            0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x22, 0x22,
+            0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22,
+            0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22,
+            0x22, 0x22, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33,
+            0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33,
+            0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44,
+            0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44,
+            0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x55, 0x55, 0x55, 0x55,
+            0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55,
+            0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55, 0x55,
+            0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+            0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66, 0x66,
+            0x66, 0x66, 0x66, 0x66, 0xef, 0xbe, 0xad, 0xde, 0xef, 0xcd, 0xab, 0x00, 0xc7, 0xcf,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01, 0x00, 0x00, 0x00,
+        ];
+        assert_eq!(&components.to_bytes()[..], &expected_preimage[..]);
+
+        let mut personal = [0; 16];
+        (&mut personal[..12]).copy_from_slice(ZCASH_SIGHASH_PERSONALIZATION_PREFIX);
+        (&mut personal[12..])
+            .write_u32::<LittleEndian>(components.consensus_branch_id)
+            .unwrap();
+        let mut h = Blake2b::with_params(32, &[], &[], &personal);
+        h.update(&components.to_bytes());
+
+        let expected_hash: [u8; 32] = [
+            0x8f, 0xdb, 0x22, 0xba, 0x94, 0xb1, 0x05, 0xd7, 0x9e, 0x42, 0xf0, 0xcb, 0x55, 0xf4,
+            0xaf, 0xdc, 0x56, 0x72, 0xde, 0xaf, 0xed, 0x3a, 0x8e, 0xf4, 0x4d, 0x17, 0x39, 0x98,
+            0xb0, 0x8d, 0xf4, 0x71,
+        ];
+        assert_eq!(h.finalize().as_ref(), &expected_hash[..]);
+    }
+
+    /// Known-answer test for the ZIP-143 (Overwinter) preimage and final
+    /// sighash, same fixture as `sapling_preimage_and_signature_hash_known_answer`
+    /// but with `is_sapling: false` — demonstrates that the shielded hashes
+    /// and `value_balance` are omitted from the preimage entirely, rather
+    /// than zero-filled, for non-Sapling transactions.
+    #[test]
+    fn overwinter_preimage_and_signature_hash_known_answer() {
+        let components = SigHashComponents {
+            consensus_branch_id: 0x76b8_09bb,
+            header: 0x8000_0004,
+            version_group_id: 0x892f_2085,
+            prevouts_hash: vec![0x11; 32],
+            sequence_hash: vec![0x22; 32],
+            outputs_hash: vec![0x33; 32],
+            joinsplits_hash: vec![0x44; 32],
+            shielded_spends_hash: vec![0x55; 32],
+            shielded_outputs_hash: vec![0x66; 32],
+            lock_time: 0xdead_beef,
+            expiry_height: 0x00ab_cdef,
+            value_balance: -12345,
+            hash_type: 1,
+            is_sapling: false,
+        };
+
+        let expected_preimage: [u8; 148] = [
+            0x04, 0x00, 0x00, 0x80, 0x85, 0x20, 0x2f, 0x89, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+            0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11,
+            0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x22, 0x22,
+            0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22,
+            0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22, 0x22,
+            0x22, 0x22, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33,
+            0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x33,
+            0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44,
+            0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44,
+            0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0x44, 0xef, 0xbe, 0xad, 0xde,
+            0xef, 0xcd, 0xab, 0x00, 0x01, 0x00, 0x00, 0x00,
+        ];
+        assert_eq!(&components.to_bytes()[..], &expected_preimage[..]);
+
+        let mut personal = [0; 16];
+        (&mut personal[..12]).copy_from_slice(ZCASH_SIGHASH_PERSONALIZATION_PREFIX);
+        (&mut personal[12..])
+            .write_u32::<LittleEndian>(components.consensus_branch_id)
+            .unwrap();
+        let mut h = Blake2b::with_params(32, &[], &[], &personal);
+        h.update(&components.to_bytes());
+
+        let expected_hash: [u8; 32] = [
+            0xc3, 0xef, 0x99, 0x20, 0x1b, 0x11, 0x1c, 0x31, 0xd1, 0xbd, 0xec, 0x12, 0x42, 0xd6,
+            0x23, 0xbd, 0xd8, 0xe0, 0x24, 0x03, 0x5e, 0xca, 0xb7, 0xfe, 0x77, 0xb1, 0x2c, 0x61,
+            0x42, 0xb9, 0x7a, 0xa2,
+        ];
+        assert_eq!(h.finalize().as_ref(), &expected_hash[..]);
+    }
+
+    /// End-to-end known-answer test for `signature_hash` itself on an actual
+    /// Overwinter-version (non-Sapling) transaction: regression coverage for
+    /// the Sapling-only fields being fed into every Overwinter preimage (see
+    /// `SigHashComponents::to_bytes`).
+    #[test]
+    fn signature_hash_overwinter_transaction_known_answer() {
+        let tx = Transaction {
+            overwintered: true,
+            version: 3,
+            version_group_id: OVERWINTER_VERSION_GROUP_ID,
+            vin: vec![],
+            vout: vec![],
+            lock_time: 0,
+            expiry_height: 0,
+            value_balance: 0,
+            shielded_spends: vec![],
+            shielded_outputs: vec![],
+            joinsplits: vec![],
+            joinsplit_pubkey: [0; 32],
+            joinsplit_sig: None,
+            binding_sig: None,
+        };
+
+        let sighash = signature_hash(&tx, 0x5ba8_1b19, SIGHASH_ALL, None);
+
+        let expected_hash: [u8; 32] = [
+            0xc3, 0x99, 0xab, 0x86, 0x91, 0x21, 0xe3, 0x71, 0x2d, 0x29, 0xf7, 0xa6, 0x7d, 0xc8,
+            0x66, 0xb7, 0x65, 0x34, 0x80, 0xe0, 0x81, 0x13, 0xe0, 0x82, 0x5c, 0xd4, 0x6f, 0xf6,
+            0xbc, 0x09, 0x2c, 0xff,
+        ];
+        assert_eq!(&sighash[..], &expected_hash[..]);
+    }
+
+    fn dummy_sprout_tx() -> Transaction {
+        Transaction {
+            overwintered: false,
+            version: 1,
+            version_group_id: 0,
+            vin: vec![TxIn {
+                prevout: OutPoint {
+                    hash: [7; 32],
+                    n: 0,
+                },
+                script_sig: Script(vec![]),
+                sequence: 0xffff_ffff,
+            }],
+            vout: vec![TxOut {
+                value: Amount(1000),
+                script_pubkey: Script(vec![]),
+            }],
+            lock_time: 0,
+            expiry_height: 0,
+            value_balance: 0,
+            shielded_spends: vec![],
+            shielded_outputs: vec![],
+            joinsplits: vec![],
+            joinsplit_pubkey: [0; 32],
+            joinsplit_sig: None,
+            binding_sig: None,
+        }
+    }
+
+    #[test]
+    fn sprout_sighash_all_does_not_panic() {
+        let tx = dummy_sprout_tx();
+        let input = Some((0, Script(vec![0x51]), Amount(1000)));
+        let hash = sprout_signature_hash(&tx, SIGHASH_ALL, input);
+        assert_eq!(hash.len(), 32);
+    }
+
+    #[test]
+    fn sprout_sighash_none_does_not_panic() {
+        let tx = dummy_sprout_tx();
+        let input = Some((0, Script(vec![0x51]), Amount(1000)));
+        let hash = sprout_signature_hash(&tx, SIGHASH_NONE, input);
+        assert_eq!(hash.len(), 32);
+    }
+
+    #[test]
+    fn sprout_sighash_anyonecanpay_does_not_panic() {
+        let tx = dummy_sprout_tx();
+        let input = Some((0, Script(vec![0x51]), Amount(1000)));
+        let hash = sprout_signature_hash(&tx, SIGHASH_ALL | SIGHASH_ANYONECANPAY, input);
+        assert_eq!(hash.len(), 32);
+    }
+
+    #[test]
+    fn sprout_sighash_single_with_matching_output_hashes_normally() {
+        let tx = dummy_sprout_tx();
+        let input = Some((0, Script(vec![0x51]), Amount(1000)));
+        let hash = sprout_signature_hash(&tx, SIGHASH_SINGLE, input);
+        assert_ne!(hash, {
+            let mut sentinel = vec![0u8; 32];
+            sentinel[0] = 1;
+            sentinel
+        });
+    }
+
+    #[test]
+    fn sprout_sighash_single_out_of_range_output_returns_sentinel() {
+        let tx = dummy_sprout_tx();
+        // Signing input 5 with SIGHASH_SINGLE when there is only 1 output.
+        let input = Some((5, Script(vec![0x51]), Amount(1000)));
+        let hash = sprout_signature_hash(&tx, SIGHASH_SINGLE, input);
+        let mut sentinel = vec![0u8; 32];
+        sentinel[0] = 1;
+        assert_eq!(hash, sentinel);
+    }
+
+    #[test]
+    fn sprout_sighash_single_with_no_transparent_input_returns_sentinel_without_panicking() {
+        let tx = dummy_sprout_tx();
+        let hash = sprout_signature_hash(&tx, SIGHASH_SINGLE, None);
+        let mut sentinel = vec![0u8; 32];
+        sentinel[0] = 1;
+        assert_eq!(hash, sentinel);
     }
 }
\ No newline at end of file